@@ -0,0 +1,142 @@
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use walkdir::WalkDir;
+
+use crate::chapters::{read_chapters_from_mkv, ChapterBackend, Chapters};
+
+/// Aggregate counts for a [`extract_chapters_dir`] run.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BatchSummary {
+    pub files_scanned: usize,
+    pub chapters_found: usize,
+    pub files_with_no_chapters: usize,
+    pub failures: usize,
+}
+
+/// Result of walking a directory and extracting chapters from every matching
+/// file: the per-file outcomes plus an aggregate [`BatchSummary`].
+pub struct BatchResult {
+    pub results: Vec<(PathBuf, anyhow::Result<Chapters>)>,
+    pub summary: BatchSummary,
+}
+
+/// Walk `root`, extracting chapters (via `backend`) from every file whose
+/// extension (without the leading dot, matched case-insensitively) is in
+/// `extensions`, in parallel. A file that fails to extract doesn't abort the
+/// rest of the run — its error is collected alongside the successes.
+///
+/// Pass [`ChapterBackend::Libav`] to process a mixed library of containers
+/// (MP4, MKV, WebM, ...); [`ChapterBackend::MkvToolnix`] only ever succeeds
+/// on `.mkv` files.
+pub fn extract_chapters_dir(
+    root: impl AsRef<Path>,
+    extensions: &[&str],
+    backend: ChapterBackend,
+) -> BatchResult {
+    let files: Vec<PathBuf> = WalkDir::new(root)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .map(|ext| {
+                    extensions
+                        .iter()
+                        .any(|wanted| wanted.eq_ignore_ascii_case(ext))
+                })
+                .unwrap_or(false)
+        })
+        .collect();
+
+    let results: Vec<(PathBuf, anyhow::Result<Chapters>)> = files
+        .into_par_iter()
+        .map(|path| {
+            let chapters = read_chapters_from_mkv(&path, backend);
+            (path, chapters)
+        })
+        .collect();
+
+    let mut summary = BatchSummary {
+        files_scanned: results.len(),
+        ..Default::default()
+    };
+    for (_, result) in &results {
+        match result {
+            Ok(chapters) => {
+                let count = chapters.iter().count();
+                summary.chapters_found += count;
+                if count == 0 {
+                    summary.files_with_no_chapters += 1;
+                }
+            }
+            Err(_) => summary.failures += 1,
+        }
+    }
+
+    BatchResult { results, summary }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A fresh, empty directory under the system temp dir for one test.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "zaohelp_batch_test_{name}_{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("failed to create scratch dir");
+        dir
+    }
+
+    #[test]
+    fn only_scans_files_with_matching_extensions() {
+        let dir = scratch_dir("extensions");
+        fs::write(dir.join("a.mkv"), b"").unwrap();
+        fs::write(dir.join("b.MP4"), b"").unwrap();
+        fs::write(dir.join("c.txt"), b"").unwrap();
+
+        let result = extract_chapters_dir(&dir, &["mkv", "mp4"], ChapterBackend::MkvToolnix);
+
+        assert_eq!(result.summary.files_scanned, 2);
+        assert_eq!(result.results.len(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn every_scanned_file_gets_a_result() {
+        let dir = scratch_dir("results");
+        fs::write(dir.join("broken.mkv"), b"not actually mkv").unwrap();
+
+        let result = extract_chapters_dir(&dir, &["mkv"], ChapterBackend::MkvToolnix);
+
+        assert_eq!(result.summary.files_scanned, 1);
+        assert_eq!(result.results.len(), 1);
+        // A file that isn't really an MKV can't yield chapters, but the bad
+        // file must be reported as a failure, not silently dropped.
+        assert_eq!(result.summary.failures, 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn empty_directory_scans_nothing() {
+        let dir = scratch_dir("empty");
+
+        let result = extract_chapters_dir(&dir, &["mkv"], ChapterBackend::MkvToolnix);
+
+        assert_eq!(result.summary.files_scanned, 0);
+        assert!(result.results.is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
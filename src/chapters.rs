@@ -1,178 +1,591 @@
-#![allow(dead_code)]
-
-use anyhow::Context;
-use serde::{Deserialize, Serialize};
-use serde_xml_rs::de::from_str;
-use std::ffi::{OsStr, OsString};
-use std::fmt::{Display, Write};
-use std::fs::File;
-use std::path::{Path, PathBuf};
-use std::{fs, process::Command};
-
-use crate::temp::{copy_to_temp, create_temp_file};
-use crate::utils::get_third_party_binary;
-
-pub fn extract_chapters(mkv_file_path: impl AsRef<Path>) -> anyhow::Result<Chapters> {
-    let (_temp_dir, temp_file) =
-        create_temp_file("chapters.xml").expect("failed to create temp file");
-    let _ = fs::remove_file(&temp_file);
-
-    let tool_path = get_third_party_binary("mkvextract.exe");
-
-    let status = Command::new(tool_path)
-        .arg(mkv_file_path.as_ref())
-        .arg("chapters")
-        .arg(&temp_file)
-        .status()?;
-
-    if !status.success() {
-        anyhow::bail!(
-            "Failed to extract chapters from {}",
-            mkv_file_path.as_ref().display()
-        );
-    }
-
-    let metadata = fs::metadata(&temp_file)?;
-    if metadata.len() == 0 {
-        anyhow::bail!("Chapters not found in {}", mkv_file_path.as_ref().display());
-    }
-
-    let xml_content = std::fs::read_to_string(&temp_file)?;
-    let chapters = parse_chapter_xml(&xml_content)?;
-
-    Ok(chapters)
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-pub struct Chapters {
-    #[serde(rename = "EditionEntry")]
-    edition_entry: EditionEntry,
-}
-
-impl Chapters {
-    pub fn iter(&self) -> impl Iterator<Item = &ChapterAtom> {
-        self.edition_entry.chapters.iter()
-    }
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut ChapterAtom> {
-        self.edition_entry.chapters.iter_mut()
-    }
-
-    pub fn to_os_string(&self) -> OsString {
-        let mut output = String::new();
-
-        for chapter in self {
-            let _ = writeln!(
-                &mut output,
-                "Start: {:<12} End: {:<12} Title: {}",
-                chapter.start_time,
-                chapter
-                    .end_time
-                    .clone()
-                    .unwrap_or_else(|| "???".to_string()),
-                chapter.display.title
-            );
-        }
-
-        OsString::from(output)
-    }
-}
-
-impl<'a> IntoIterator for &'a Chapters {
-    type Item = &'a ChapterAtom;
-    type IntoIter = std::slice::Iter<'a, ChapterAtom>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        self.edition_entry.chapters.iter()
-    }
-}
-impl<'a> IntoIterator for &'a mut Chapters {
-    type Item = &'a mut ChapterAtom;
-    type IntoIter = std::slice::IterMut<'a, ChapterAtom>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        self.edition_entry.chapters.iter_mut()
-    }
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-pub struct EditionEntry {
-    #[serde(rename = "ChapterAtom", default)]
-    chapters: Vec<ChapterAtom>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-pub struct ChapterAtom {
-    #[serde(rename = "ChapterTimeStart")]
-    pub start_time: String,
-
-    #[serde(rename = "ChapterTimeEnd")]
-    pub end_time: Option<String>,
-
-    #[serde(rename = "ChapterDisplay")]
-    pub display: ChapterDisplay,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-pub struct ChapterDisplay {
-    #[serde(rename = "ChapterString")]
-    pub title: String,
-}
-
-pub fn parse_chapter_xml(xml: &str) -> anyhow::Result<Chapters> {
-    let chapters: Chapters = from_str(xml)?;
-    Ok(chapters)
-}
-
-pub fn read_chapters_from_mkv(mkv_file: impl AsRef<Path>) -> anyhow::Result<Chapters> {
-    let chapters = extract_chapters(&mkv_file)?;
-
-    // for chapter in &chapters {
-    //     println!(
-    //         "File: {}, Start: {}, End: {:?}, Title: {}",
-    //         &mkv_file.as_ref().file_name().unwrap().display(),
-    //         chapter.start_time,
-    //         chapter.end_time,
-    //         chapter.display.title
-    //     );
-    // }
-
-    Ok(chapters)
-}
-
-fn chapters_to_xml(chapters: &Chapters) -> anyhow::Result<String> {
-    let inner = serde_xml_rs::to_string(chapters)?;
-    Ok(format!(r#"<?xml version="1.0"?>\n{}"#, inner))
-}
-
-pub fn add_chapter_to_mkv(mkv_file: &str, timestamp: &str, title: &str) -> anyhow::Result<()> {
-    let mut chapters = extract_chapters(mkv_file)?;
-
-    let new_chapter = ChapterAtom {
-        start_time: timestamp.to_string(),
-        end_time: None,
-        display: ChapterDisplay {
-            title: title.to_string(),
-        },
-    };
-    chapters.edition_entry.chapters.push(new_chapter);
-
-    let xml_output = chapters_to_xml(&chapters)?;
-
-    use std::io::Write;
-    let (_temp_dir, temp_file) = create_temp_file("add_chapter_to_mkv_chapters.xml")?;
-    let mut file = File::create(&temp_file)?;
-    file.write_all(xml_output.as_bytes())?;
-
-    // Step 6: Apply changes via mkvpropedit
-    let status = Command::new("third_party/bin/mkvpropedit.exe")
-        .arg(mkv_file)
-        .arg("--chapters")
-        .arg(&temp_file)
-        .status()?;
-
-    if !status.success() {
-        anyhow::bail!("Failed to apply chapters with mkvpropedit");
-    }
-
-    Ok(())
-}
+#![allow(dead_code)]
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use rand::Rng;
+use std::ffi::OsString;
+use std::fmt::Write;
+use std::path::Path;
+use std::{fs, process::Command};
+
+use crate::temp::create_temp_file;
+use crate::time::{parse_time, Time};
+use crate::utils::get_third_party_binary;
+
+pub fn extract_chapters(mkv_file_path: impl AsRef<Path>) -> anyhow::Result<Chapters> {
+    let (_temp_dir, temp_file) =
+        create_temp_file("chapters.xml").expect("failed to create temp file");
+    let _ = fs::remove_file(&temp_file);
+
+    let tool_path = get_third_party_binary("mkvextract.exe");
+
+    let status = Command::new(tool_path)
+        .arg(mkv_file_path.as_ref())
+        .arg("chapters")
+        .arg(&temp_file)
+        .status()?;
+
+    if !status.success() {
+        anyhow::bail!(
+            "Failed to extract chapters from {}",
+            mkv_file_path.as_ref().display()
+        );
+    }
+
+    let metadata = fs::metadata(&temp_file)?;
+    if metadata.len() == 0 {
+        anyhow::bail!("Chapters not found in {}", mkv_file_path.as_ref().display());
+    }
+
+    let xml_content = std::fs::read_to_string(&temp_file)?;
+    let chapters = parse_chapter_xml(&xml_content)?;
+
+    Ok(chapters)
+}
+
+/// A full Matroska `Chapters` element: zero or more editions, each holding a
+/// (possibly nested) tree of `ChapterAtom`s.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Chapters {
+    editions: Vec<EditionEntry>,
+}
+
+impl Chapters {
+    /// All editions, in document order.
+    pub fn editions(&self) -> &[EditionEntry] {
+        &self.editions
+    }
+
+    pub fn editions_mut(&mut self) -> &mut [EditionEntry] {
+        &mut self.editions
+    }
+
+    /// Build a `Chapters` with a single, unnamed edition holding `atoms` —
+    /// the shape backends without a native notion of editions (e.g. ffmpeg)
+    /// normalize their output into.
+    pub(crate) fn from_single_edition(atoms: Vec<ChapterAtom>) -> Self {
+        Self {
+            editions: vec![EditionEntry {
+                atoms,
+                ..Default::default()
+            }],
+        }
+    }
+
+    /// Insert a new top-level chapter atom into the first edition, creating
+    /// one if there are none yet.
+    pub fn push_atom(&mut self, atom: ChapterAtom) {
+        if let Some(edition) = self.editions.first_mut() {
+            edition.atoms.push(atom);
+        } else {
+            self.editions.push(EditionEntry {
+                atoms: vec![atom],
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Rename the top-level chapter at flattened `index` (as listed by
+    /// [`Chapters::iter`]), returning `false` if the index is out of range.
+    pub fn rename_atom(&mut self, index: usize, title: String) -> bool {
+        let Some(atom) = self.iter_mut().nth(index) else {
+            return false;
+        };
+        if let Some(display) = atom.displays.first_mut() {
+            display.title = title;
+        } else {
+            atom.displays.push(ChapterDisplay {
+                title,
+                ..Default::default()
+            });
+        }
+        true
+    }
+
+    /// Remove the top-level chapter at flattened `index`.
+    pub fn remove_atom(&mut self, index: usize) -> Option<ChapterAtom> {
+        let mut remaining = index;
+        for edition in &mut self.editions {
+            if remaining < edition.atoms.len() {
+                return Some(edition.atoms.remove(remaining));
+            }
+            remaining -= edition.atoms.len();
+        }
+        None
+    }
+
+    /// Top-level chapter atoms across all editions, flattened. Kept for
+    /// back-compat with callers that only care about a single flat list;
+    /// nested sub-chapters are not included here, see [`Chapters::walk`].
+    pub fn iter(&self) -> impl Iterator<Item = &ChapterAtom> {
+        self.editions.iter().flat_map(|edition| edition.atoms.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut ChapterAtom> {
+        self.editions
+            .iter_mut()
+            .flat_map(|edition| edition.atoms.iter_mut())
+    }
+
+    /// Depth-first walk over every atom in every edition, including nested
+    /// sub-chapters.
+    pub fn walk(&self) -> impl Iterator<Item = &ChapterAtom> {
+        fn visit<'a>(atoms: &'a [ChapterAtom], out: &mut Vec<&'a ChapterAtom>) {
+            for atom in atoms {
+                out.push(atom);
+                visit(&atom.children, out);
+            }
+        }
+
+        let mut out = Vec::new();
+        for edition in &self.editions {
+            visit(&edition.atoms, &mut out);
+        }
+        out.into_iter()
+    }
+
+    pub fn to_os_string(&self) -> OsString {
+        let mut output = String::new();
+
+        for chapter in self.iter() {
+            let _ = writeln!(
+                &mut output,
+                "Start: {:<12} End: {:<12} Title: {}",
+                chapter.start_time.to_string(),
+                chapter
+                    .end_time
+                    .map(|t| t.to_string())
+                    .unwrap_or_else(|| "???".to_string()),
+                chapter
+                    .displays
+                    .first()
+                    .map(|d| d.title.as_str())
+                    .unwrap_or("???"),
+            );
+        }
+
+        OsString::from(output)
+    }
+}
+
+impl<'a> IntoIterator for &'a Chapters {
+    type Item = &'a ChapterAtom;
+    type IntoIter = Box<dyn Iterator<Item = &'a ChapterAtom> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+impl<'a> IntoIterator for &'a mut Chapters {
+    type Item = &'a mut ChapterAtom;
+    type IntoIter = Box<dyn Iterator<Item = &'a mut ChapterAtom> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter_mut())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EditionEntry {
+    pub uid: Option<String>,
+    pub hidden: bool,
+    pub atoms: Vec<ChapterAtom>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChapterAtom {
+    pub uid: Option<String>,
+    pub start_time: Time,
+    pub end_time: Option<Time>,
+    pub hidden: bool,
+    pub enabled: bool,
+    pub displays: Vec<ChapterDisplay>,
+    pub children: Vec<ChapterAtom>,
+}
+
+impl Default for ChapterAtom {
+    fn default() -> Self {
+        Self {
+            uid: None,
+            start_time: Time::default(),
+            end_time: None,
+            // Matroska defaults both flags to enabled/visible.
+            hidden: false,
+            enabled: true,
+            displays: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChapterDisplay {
+    pub title: String,
+    pub language: Option<String>,
+    pub country: Option<String>,
+}
+
+/// Parse a Matroska chapter XML document into a full [`Chapters`] tree.
+///
+/// Reads the document as a stream of `quick_xml` events rather than via a
+/// single `serde` deserialize pass, so multiple `EditionEntry` elements,
+/// nested `ChapterAtom` sub-chapters, and per-language `ChapterDisplay`
+/// blocks all round-trip instead of only the first of each being kept.
+pub fn parse_chapter_xml(xml: &str) -> anyhow::Result<Chapters> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut editions = Vec::new();
+    let mut current_edition: Option<EditionEntry> = None;
+    let mut atom_stack: Vec<ChapterAtom> = Vec::new();
+    let mut display_stack: Vec<ChapterDisplay> = Vec::new();
+    let mut text = String::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(start) => {
+                text.clear();
+                match start.name().as_ref() {
+                    b"EditionEntry" => current_edition = Some(EditionEntry::default()),
+                    b"ChapterAtom" => atom_stack.push(ChapterAtom::default()),
+                    b"ChapterDisplay" => display_stack.push(ChapterDisplay::default()),
+                    _ => {}
+                }
+            }
+            Event::Text(t) => text.push_str(&t.unescape()?),
+            Event::End(end) => {
+                let value = std::mem::take(&mut text);
+                match end.name().as_ref() {
+                    b"EditionUID" => {
+                        if let Some(edition) = current_edition.as_mut() {
+                            edition.uid = Some(value);
+                        }
+                    }
+                    b"EditionFlagHidden" => {
+                        if let Some(edition) = current_edition.as_mut() {
+                            edition.hidden = value.trim() == "1";
+                        }
+                    }
+                    b"ChapterUID" => {
+                        if let Some(atom) = atom_stack.last_mut() {
+                            atom.uid = Some(value);
+                        }
+                    }
+                    b"ChapterTimeStart" => {
+                        if let Some(atom) = atom_stack.last_mut() {
+                            atom.start_time = parse_time(&value)?;
+                        }
+                    }
+                    b"ChapterTimeEnd" => {
+                        if let Some(atom) = atom_stack.last_mut() {
+                            atom.end_time = Some(parse_time(&value)?);
+                        }
+                    }
+                    b"ChapterFlagHidden" => {
+                        if let Some(atom) = atom_stack.last_mut() {
+                            atom.hidden = value.trim() == "1";
+                        }
+                    }
+                    b"ChapterFlagEnabled" => {
+                        if let Some(atom) = atom_stack.last_mut() {
+                            atom.enabled = value.trim() == "1";
+                        }
+                    }
+                    b"ChapterString" => {
+                        if let Some(display) = display_stack.last_mut() {
+                            display.title = value;
+                        }
+                    }
+                    b"ChapterLanguage" => {
+                        if let Some(display) = display_stack.last_mut() {
+                            display.language = Some(value);
+                        }
+                    }
+                    b"ChapterCountry" => {
+                        if let Some(display) = display_stack.last_mut() {
+                            display.country = Some(value);
+                        }
+                    }
+                    b"ChapterDisplay" => {
+                        if let Some(display) = display_stack.pop() {
+                            if let Some(atom) = atom_stack.last_mut() {
+                                atom.displays.push(display);
+                            }
+                        }
+                    }
+                    b"ChapterAtom" => {
+                        if let Some(atom) = atom_stack.pop() {
+                            if let Some(parent) = atom_stack.last_mut() {
+                                parent.children.push(atom);
+                            } else if let Some(edition) = current_edition.as_mut() {
+                                edition.atoms.push(atom);
+                            }
+                        }
+                    }
+                    b"EditionEntry" => {
+                        if let Some(edition) = current_edition.take() {
+                            editions.push(edition);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(Chapters { editions })
+}
+
+/// Which tool reads chapters out of a media file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChapterBackend {
+    /// Shell out to the bundled mkvtoolnix `mkvextract` binary. MKV-only.
+    #[default]
+    MkvToolnix,
+    /// Read chapters via `ffmpeg-the-third`. Works for any container ffmpeg
+    /// supports (MP4, MKV, WebM, ...).
+    Libav,
+}
+
+pub fn read_chapters_from_mkv(
+    mkv_file: impl AsRef<Path>,
+    backend: ChapterBackend,
+) -> anyhow::Result<Chapters> {
+    let chapters = match backend {
+        ChapterBackend::MkvToolnix => extract_chapters(&mkv_file)?,
+        ChapterBackend::Libav => crate::ffmpeg_chapters::extract_chapters_libav(&mkv_file)?,
+    };
+
+    // for chapter in &chapters {
+    //     println!(
+    //         "File: {}, Start: {}, End: {:?}, Title: {}",
+    //         &mkv_file.as_ref().file_name().unwrap().display(),
+    //         chapter.start_time,
+    //         chapter.end_time,
+    //         chapter.display.title
+    //     );
+    // }
+
+    Ok(chapters)
+}
+
+fn escape_xml_text(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// A `ChapterUID` is just a Matroska-wide unique random 64-bit identifier;
+/// there's nothing to look up, so we mint a fresh one for any atom that
+/// doesn't already carry one from the source file.
+fn generate_chapter_uid() -> String {
+    rand::thread_rng().gen::<u64>().to_string()
+}
+
+fn write_chapter_atom(out: &mut String, atom: &ChapterAtom, indent: usize) {
+    let pad = "  ".repeat(indent);
+    let uid = atom.uid.clone().unwrap_or_else(generate_chapter_uid);
+
+    writeln!(out, "{pad}<ChapterAtom>").unwrap();
+    writeln!(out, "{pad}  <ChapterUID>{uid}</ChapterUID>").unwrap();
+    writeln!(
+        out,
+        "{pad}  <ChapterTimeStart>{}</ChapterTimeStart>",
+        atom.start_time
+    )
+    .unwrap();
+    if let Some(end_time) = &atom.end_time {
+        writeln!(out, "{pad}  <ChapterTimeEnd>{end_time}</ChapterTimeEnd>").unwrap();
+    }
+    writeln!(
+        out,
+        "{pad}  <ChapterFlagHidden>{}</ChapterFlagHidden>",
+        atom.hidden as u8
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "{pad}  <ChapterFlagEnabled>{}</ChapterFlagEnabled>",
+        atom.enabled as u8
+    )
+    .unwrap();
+
+    for display in &atom.displays {
+        writeln!(out, "{pad}  <ChapterDisplay>").unwrap();
+        writeln!(
+            out,
+            "{pad}    <ChapterString>{}</ChapterString>",
+            escape_xml_text(&display.title)
+        )
+        .unwrap();
+        if let Some(language) = &display.language {
+            writeln!(
+                out,
+                "{pad}    <ChapterLanguage>{}</ChapterLanguage>",
+                escape_xml_text(language)
+            )
+            .unwrap();
+        }
+        if let Some(country) = &display.country {
+            writeln!(
+                out,
+                "{pad}    <ChapterCountry>{}</ChapterCountry>",
+                escape_xml_text(country)
+            )
+            .unwrap();
+        }
+        writeln!(out, "{pad}  </ChapterDisplay>").unwrap();
+    }
+
+    for child in &atom.children {
+        write_chapter_atom(out, child, indent + 1);
+    }
+
+    writeln!(out, "{pad}</ChapterAtom>").unwrap();
+}
+
+/// Serialize a [`Chapters`] tree back into Matroska chapter XML, preserving
+/// every edition, nested sub-chapter and per-language display that
+/// [`parse_chapter_xml`] produced. New atoms without a `ChapterUID` get a
+/// freshly generated one; existing UIDs are carried through unchanged.
+fn chapters_to_xml(chapters: &Chapters) -> anyhow::Result<String> {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<!DOCTYPE Chapters SYSTEM \"matroskachapters.dtd\">\n");
+    out.push_str("<Chapters>\n");
+
+    for edition in &chapters.editions {
+        out.push_str("  <EditionEntry>\n");
+        if let Some(uid) = &edition.uid {
+            writeln!(out, "    <EditionUID>{uid}</EditionUID>").unwrap();
+        }
+        writeln!(
+            out,
+            "    <EditionFlagHidden>{}</EditionFlagHidden>",
+            edition.hidden as u8
+        )
+        .unwrap();
+        for atom in &edition.atoms {
+            write_chapter_atom(&mut out, atom, 2);
+        }
+        out.push_str("  </EditionEntry>\n");
+    }
+
+    out.push_str("</Chapters>\n");
+    Ok(out)
+}
+
+/// Write an edited [`Chapters`] tree back to `mkv_file` via `mkvpropedit`,
+/// replacing its chapters in place. Round-trips the full model (multiple
+/// editions, nested atoms, per-language displays, UIDs) without the data
+/// loss `add_chapter_to_mkv` used to silently incur.
+pub fn write_chapters_to_mkv(mkv_file: impl AsRef<Path>, chapters: &Chapters) -> anyhow::Result<()> {
+    let xml_output = chapters_to_xml(chapters)?;
+
+    let (_temp_dir, temp_file) = create_temp_file("write_chapters_to_mkv.xml")?;
+    fs::write(&temp_file, xml_output)?;
+
+    let tool_path = get_third_party_binary("mkvpropedit.exe");
+    let status = Command::new(tool_path)
+        .arg(mkv_file.as_ref())
+        .arg("--chapters")
+        .arg(&temp_file)
+        .status()?;
+
+    if !status.success() {
+        anyhow::bail!(
+            "Failed to apply chapters to {}",
+            mkv_file.as_ref().display()
+        );
+    }
+
+    Ok(())
+}
+
+pub fn add_chapter_to_mkv(mkv_file: &str, timestamp: &str, title: &str) -> anyhow::Result<()> {
+    let mut chapters = extract_chapters(mkv_file)?;
+
+    let new_chapter = ChapterAtom {
+        start_time: parse_time(timestamp)?,
+        displays: vec![ChapterDisplay {
+            title: title.to_string(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    chapters.push_atom(new_chapter);
+
+    write_chapters_to_mkv(mkv_file, &chapters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_XML: &str = r#"<?xml version="1.0"?>
+<Chapters>
+  <EditionEntry>
+    <EditionUID>1111111111</EditionUID>
+    <ChapterAtom>
+      <ChapterUID>2222222222</ChapterUID>
+      <ChapterTimeStart>00:00:00.000000000</ChapterTimeStart>
+      <ChapterTimeEnd>00:05:00.000000000</ChapterTimeEnd>
+      <ChapterFlagHidden>0</ChapterFlagHidden>
+      <ChapterFlagEnabled>1</ChapterFlagEnabled>
+      <ChapterDisplay>
+        <ChapterString>Intro</ChapterString>
+        <ChapterLanguage>eng</ChapterLanguage>
+      </ChapterDisplay>
+      <ChapterDisplay>
+        <ChapterString>Intro FR</ChapterString>
+        <ChapterLanguage>fre</ChapterLanguage>
+        <ChapterCountry>fr</ChapterCountry>
+      </ChapterDisplay>
+      <ChapterAtom>
+        <ChapterUID>3333333333</ChapterUID>
+        <ChapterTimeStart>00:02:00.000000000</ChapterTimeStart>
+        <ChapterFlagHidden>0</ChapterFlagHidden>
+        <ChapterFlagEnabled>1</ChapterFlagEnabled>
+        <ChapterDisplay>
+          <ChapterString>Intro, part two</ChapterString>
+        </ChapterDisplay>
+      </ChapterAtom>
+    </ChapterAtom>
+  </EditionEntry>
+</Chapters>"#;
+
+    #[test]
+    fn round_trips_nested_multi_language_chapters_through_xml() {
+        let parsed = parse_chapter_xml(SAMPLE_XML).expect("sample XML should parse");
+
+        let serialized = chapters_to_xml(&parsed).expect("parsed chapters should serialize");
+        let reparsed = parse_chapter_xml(&serialized).expect("serialized XML should re-parse");
+
+        assert_eq!(parsed, reparsed);
+    }
+
+    #[test]
+    fn escapes_special_characters_in_language_and_country_not_just_title() {
+        let chapters = Chapters::from_single_edition(vec![ChapterAtom {
+            uid: Some("123456789".to_string()),
+            displays: vec![ChapterDisplay {
+                title: "A & B <title>".to_string(),
+                language: Some("eng & fre".to_string()),
+                country: Some("us <\"x\">".to_string()),
+            }],
+            ..Default::default()
+        }]);
+
+        let serialized = chapters_to_xml(&chapters).expect("chapters should serialize");
+        let reparsed =
+            parse_chapter_xml(&serialized).expect("serialized XML should re-parse without error");
+
+        assert_eq!(chapters, reparsed);
+    }
+}
@@ -0,0 +1,128 @@
+#![allow(dead_code)]
+
+use std::io::{self, Write};
+
+use crate::chapters::{
+    read_chapters_from_mkv, write_chapters_to_mkv, ChapterAtom, ChapterBackend, ChapterDisplay,
+};
+use crate::time::{parse_time_with_fps, Time};
+
+/// Prompt `question`, looping until the user enters a non-empty line.
+pub fn ask(question: &str) -> String {
+    loop {
+        print!("{question}: ");
+        let _ = io::stdout().flush();
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).is_err() {
+            eprintln!("Failed to read input, please try again.");
+            continue;
+        }
+
+        let input = input.trim();
+        if input.is_empty() {
+            eprintln!("Please enter a value.");
+            continue;
+        }
+
+        return input.to_string();
+    }
+}
+
+/// Prompt `question`, looping until the user enters a timestamp that
+/// [`parse_time_with_fps`] accepts. `fps`, when set, lets the user type a
+/// bare integer frame count instead of a duration.
+pub fn ask_time(question: &str, fps: Option<f64>) -> Time {
+    loop {
+        let input = ask(question);
+        match parse_time_with_fps(&input, fps) {
+            Ok(time) => return time,
+            Err(err) => eprintln!("Invalid timestamp '{input}': {err}"),
+        }
+    }
+}
+
+/// Prompt for an optional frame rate, returning `None` on a blank answer.
+fn ask_fps() -> Option<f64> {
+    print!("Video frame rate (for frame-number timestamps, blank to skip): ");
+    let _ = io::stdout().flush();
+
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input).is_err() {
+        return None;
+    }
+    input.trim().parse::<f64>().ok()
+}
+
+fn ask_yes_no(question: &str) -> bool {
+    loop {
+        match ask(&format!("{question} (y/n)")).to_lowercase().as_str() {
+            "y" | "yes" => return true,
+            "n" | "no" => return false,
+            _ => eprintln!("Please answer y or n."),
+        }
+    }
+}
+
+/// Interactive chapter authoring session for `mkv_file`: lists the current
+/// chapters, then repeatedly lets the user add, rename, or delete a chapter
+/// until they choose to save (or discard) their edits.
+pub fn edit_chapters_interactive(mkv_file: &str) -> anyhow::Result<()> {
+    let mut chapters = read_chapters_from_mkv(mkv_file, ChapterBackend::MkvToolnix)?;
+    let fps = ask_fps();
+
+    loop {
+        print!("{}", chapters.to_os_string().to_string_lossy());
+        match ask("[a]dd, [r]ename, [d]elete, [s]ave, [q]uit")
+            .to_lowercase()
+            .as_str()
+        {
+            "a" | "add" => {
+                let start_time = ask_time("Chapter start time", fps);
+                let title = ask("Chapter title");
+                chapters.push_atom(ChapterAtom {
+                    start_time,
+                    displays: vec![ChapterDisplay {
+                        title,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                });
+            }
+            "r" | "rename" => {
+                let index: usize = match ask("Chapter index to rename").parse() {
+                    Ok(index) => index,
+                    Err(_) => {
+                        eprintln!("Please enter a chapter number.");
+                        continue;
+                    }
+                };
+                let title = ask("New title");
+                if !chapters.rename_atom(index, title) {
+                    eprintln!("No chapter at index {index}.");
+                }
+            }
+            "d" | "delete" => {
+                let index: usize = match ask("Chapter index to delete").parse() {
+                    Ok(index) => index,
+                    Err(_) => {
+                        eprintln!("Please enter a chapter number.");
+                        continue;
+                    }
+                };
+                if chapters.remove_atom(index).is_none() {
+                    eprintln!("No chapter at index {index}.");
+                }
+            }
+            "s" | "save" => {
+                if ask_yes_no("Write these chapters to the file?") {
+                    write_chapters_to_mkv(mkv_file, &chapters)?;
+                    println!("Chapters saved.");
+                }
+                return Ok(());
+            }
+            "q" | "quit" => return Ok(()),
+            _ => eprintln!("Unrecognized action."),
+        }
+    }
+}
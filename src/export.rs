@@ -0,0 +1,187 @@
+#![allow(dead_code)]
+
+use std::fmt::Write as _;
+use std::io::{self, Write as IoWrite};
+
+use crate::chapters::Chapters;
+use crate::time::Time;
+
+/// Interchange formats [`Chapters::export`] can emit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// ffmpeg's `;FFMETADATA1` chapter metadata format.
+    FfMetadata,
+    /// WebVTT cues, one per chapter.
+    WebVtt,
+    /// The `0:00 Title` format used for YouTube video descriptions.
+    YouTube,
+}
+
+impl Chapters {
+    /// Render as ffmpeg's `;FFMETADATA1` format: one `[CHAPTER]` block per
+    /// chapter with a nanosecond `TIMEBASE` and integer `START`/`END`.
+    pub fn to_ffmetadata(&self) -> String {
+        let mut out = String::new();
+        out.push_str(";FFMETADATA1\n");
+
+        for chapter in self.iter() {
+            out.push_str("[CHAPTER]\n");
+            let _ = writeln!(out, "TIMEBASE=1/1000000000");
+            let _ = writeln!(out, "START={}", chapter.start_time.as_nanos());
+            let end = chapter.end_time.unwrap_or(chapter.start_time);
+            let _ = writeln!(out, "END={}", end.as_nanos());
+            let _ = writeln!(out, "title={}", chapter_title(chapter));
+        }
+
+        out
+    }
+
+    /// Render as a WebVTT cue list, one cue per chapter.
+    pub fn to_webvtt(&self) -> String {
+        let mut out = String::new();
+        out.push_str("WEBVTT\n\n");
+
+        for chapter in self.iter() {
+            let start = webvtt_timestamp(chapter.start_time);
+            let end = chapter
+                .end_time
+                .map(webvtt_timestamp)
+                .unwrap_or_else(|| start.clone());
+            let _ = writeln!(out, "{start} --> {end}\n{}\n", chapter_title(chapter));
+        }
+
+        out
+    }
+
+    /// Render as the `0:00 Title` chapter list format used in YouTube video
+    /// descriptions.
+    pub fn to_youtube_chapters(&self) -> String {
+        let mut out = String::new();
+
+        for chapter in self.iter() {
+            let _ = writeln!(
+                out,
+                "{} {}",
+                youtube_timestamp(chapter.start_time),
+                chapter_title(chapter)
+            );
+        }
+
+        out
+    }
+
+    /// Render as `format` and write the result to `writer`.
+    pub fn export(&self, format: ExportFormat, mut writer: impl IoWrite) -> io::Result<()> {
+        let content = match format {
+            ExportFormat::FfMetadata => self.to_ffmetadata(),
+            ExportFormat::WebVtt => self.to_webvtt(),
+            ExportFormat::YouTube => self.to_youtube_chapters(),
+        };
+        writer.write_all(content.as_bytes())
+    }
+}
+
+fn chapter_title(chapter: &crate::chapters::ChapterAtom) -> &str {
+    chapter
+        .displays
+        .first()
+        .map(|d| d.title.as_str())
+        .unwrap_or("")
+}
+
+fn webvtt_timestamp(time: Time) -> String {
+    let (hours, minutes, seconds, nanos) = split_time(time);
+    format!("{hours:02}:{minutes:02}:{seconds:02}.{:03}", nanos / 1_000_000)
+}
+
+fn youtube_timestamp(time: Time) -> String {
+    let (hours, minutes, seconds, _) = split_time(time);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+fn split_time(time: Time) -> (u64, u64, u64, u64) {
+    let nanos = time.as_nanos();
+    let hours = nanos / 3_600_000_000_000;
+    let remainder = nanos % 3_600_000_000_000;
+    let minutes = remainder / 60_000_000_000;
+    let remainder = remainder % 60_000_000_000;
+    let seconds = remainder / 1_000_000_000;
+    let nanos = remainder % 1_000_000_000;
+    (hours, minutes, seconds, nanos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chapters::{ChapterAtom, ChapterDisplay};
+    use crate::time::parse_time;
+
+    fn sample_chapters() -> Chapters {
+        let mut chapters = Chapters::default();
+        chapters.push_atom(ChapterAtom {
+            start_time: parse_time("00:00:00").unwrap(),
+            end_time: Some(parse_time("00:01:30").unwrap()),
+            displays: vec![ChapterDisplay {
+                title: "Intro".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        chapters.push_atom(ChapterAtom {
+            start_time: parse_time("01:05:00").unwrap(),
+            end_time: None,
+            displays: vec![ChapterDisplay {
+                title: "Finale".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        chapters
+    }
+
+    #[test]
+    fn ffmetadata_has_one_chapter_block_per_chapter() {
+        let out = sample_chapters().to_ffmetadata();
+        assert!(out.starts_with(";FFMETADATA1\n"));
+        assert_eq!(out.matches("[CHAPTER]").count(), 2);
+        assert!(out.contains("title=Intro"));
+        assert!(out.contains("title=Finale"));
+    }
+
+    #[test]
+    fn ffmetadata_uses_the_end_time_when_present_and_falls_back_to_start() {
+        let out = sample_chapters().to_ffmetadata();
+        assert!(out.contains(&format!("END={}", parse_time("00:01:30").unwrap().as_nanos())));
+        assert!(out.contains(&format!("END={}", parse_time("01:05:00").unwrap().as_nanos())));
+    }
+
+    #[test]
+    fn webvtt_has_millisecond_cue_ranges() {
+        let out = sample_chapters().to_webvtt();
+        assert!(out.starts_with("WEBVTT\n\n"));
+        assert!(out.contains("00:00:00.000 --> 00:01:30.000\nIntro"));
+    }
+
+    #[test]
+    fn youtube_format_omits_the_hour_field_below_one_hour() {
+        let out = sample_chapters().to_youtube_chapters();
+        assert!(out.contains("0:00 Intro"));
+        assert!(out.contains("1:05:00 Finale"));
+    }
+
+    #[test]
+    fn export_writes_the_selected_format_to_the_writer() {
+        let mut buf = Vec::new();
+        sample_chapters()
+            .export(ExportFormat::YouTube, &mut buf)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            sample_chapters().to_youtube_chapters()
+        );
+    }
+}
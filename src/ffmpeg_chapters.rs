@@ -0,0 +1,72 @@
+#![allow(dead_code)]
+
+use std::path::Path;
+
+use ffmpeg_the_third as ffmpeg;
+
+use crate::chapters::{ChapterAtom, ChapterDisplay, Chapters};
+use crate::time::Time;
+
+/// Read chapters directly from any container ffmpeg understands (MP4, MKV,
+/// WebM, ...) via `ffmpeg-the-third`, rather than shelling out to a
+/// mkvtoolnix binary. ffmpeg has no notion of editions, so every chapter
+/// comes back as a top-level atom in a single edition.
+pub fn extract_chapters_libav(path: impl AsRef<Path>) -> anyhow::Result<Chapters> {
+    ffmpeg::init()?;
+    let input = ffmpeg::format::input(&path.as_ref())?;
+
+    let atoms = input
+        .chapters()
+        .map(|chapter| {
+            let time_base = chapter.time_base();
+            let title = chapter
+                .metadata()
+                .get("title")
+                .map(str::to_owned)
+                .unwrap_or_default();
+
+            ChapterAtom {
+                start_time: time_to_nanos(chapter.start(), time_base),
+                end_time: Some(time_to_nanos(chapter.end(), time_base)),
+                displays: vec![ChapterDisplay {
+                    title,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    Ok(Chapters::from_single_edition(atoms))
+}
+
+/// Convert an ffmpeg chapter timestamp (an integer count of `time_base`
+/// units) into a [`Time`].
+fn time_to_nanos(timestamp: i64, time_base: ffmpeg::Rational) -> Time {
+    let seconds = timestamp as f64 * time_base.numerator() as f64 / time_base.denominator() as f64;
+    Time::from_nanos((seconds * 1_000_000_000.0).round().max(0.0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_millisecond_time_base_units() {
+        let time_base = ffmpeg::Rational::new(1, 1000);
+        assert_eq!(time_to_nanos(1_500, time_base).as_nanos(), 1_500_000_000);
+    }
+
+    #[test]
+    fn converts_standard_ffmpeg_time_base_units() {
+        // ffmpeg's default time_base is 1/1_000_000 (microseconds).
+        let time_base = ffmpeg::Rational::new(1, 1_000_000);
+        assert_eq!(time_to_nanos(2_000_000, time_base).as_nanos(), 2_000_000_000);
+    }
+
+    #[test]
+    fn negative_timestamps_clamp_to_zero() {
+        let time_base = ffmpeg::Rational::new(1, 1000);
+        assert_eq!(time_to_nanos(-5, time_base).as_nanos(), 0);
+    }
+}
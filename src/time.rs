@@ -0,0 +1,233 @@
+#![allow(dead_code)]
+
+use anyhow::Context;
+use std::fmt;
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+const NANOS_PER_MINUTE: u64 = 60 * NANOS_PER_SECOND;
+const NANOS_PER_HOUR: u64 = 60 * NANOS_PER_MINUTE;
+
+/// A chapter timestamp or duration, stored as whole nanoseconds.
+///
+/// This is the in-memory counterpart to Matroska's `HH:MM:SS.nnnnnnnnn`
+/// timestamp strings: arithmetic, ordering and duration computation all work
+/// directly on `Time`, and [`Display`](fmt::Display) renders it back to the
+/// canonical form for writing to chapter XML.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Time(u64);
+
+impl Time {
+    pub const fn from_nanos(nanos: u64) -> Self {
+        Time(nanos)
+    }
+
+    pub const fn as_nanos(self) -> u64 {
+        self.0
+    }
+
+    pub fn checked_add(self, other: Time) -> Option<Time> {
+        self.0.checked_add(other.0).map(Time)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hours = self.0 / NANOS_PER_HOUR;
+        let remainder = self.0 % NANOS_PER_HOUR;
+        let minutes = remainder / NANOS_PER_MINUTE;
+        let remainder = remainder % NANOS_PER_MINUTE;
+        let seconds = remainder / NANOS_PER_SECOND;
+        let nanos = remainder % NANOS_PER_SECOND;
+
+        f.pad(&format!("{hours:02}:{minutes:02}:{seconds:02}.{nanos:09}"))
+    }
+}
+
+/// Parse a chapter timestamp, accepting any of:
+/// - full `HH:MM:SS.fraction` (fraction is 1-9 digits, right-padded to nanoseconds)
+/// - `MM:SS(.fraction)`
+/// - bare seconds, e.g. `93.5`
+///
+/// Frame counts (an integer with no `.`/`:`) are only accepted via
+/// [`parse_time_with_fps`], since turning a frame number into a duration
+/// requires a frame rate.
+pub fn parse_time(input: &str) -> anyhow::Result<Time> {
+    parse_time_with_fps(input, None)
+}
+
+/// Like [`parse_time`], but if `input` is a bare integer (a frame count) and
+/// `fps` is given, converts it to a duration using that frame rate instead
+/// of treating it as whole seconds.
+pub fn parse_time_with_fps(input: &str, fps: Option<f64>) -> anyhow::Result<Time> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        anyhow::bail!("empty timestamp");
+    }
+
+    let parts: Vec<&str> = trimmed.split(':').collect();
+    let nanos = match parts.as_slice() {
+        [hours, minutes, seconds] => {
+            let hours: u64 = hours
+                .parse()
+                .with_context(|| format!("invalid hours in timestamp '{trimmed}'"))?;
+            let minutes: u64 = minutes
+                .parse()
+                .with_context(|| format!("invalid minutes in timestamp '{trimmed}'"))?;
+            let seconds_nanos = parse_seconds(seconds)?;
+
+            checked_sum(
+                &[
+                    checked_mul(hours, NANOS_PER_HOUR, trimmed)?,
+                    checked_mul(minutes, NANOS_PER_MINUTE, trimmed)?,
+                    seconds_nanos,
+                ],
+                trimmed,
+            )?
+        }
+        [minutes, seconds] => {
+            let minutes: u64 = minutes
+                .parse()
+                .with_context(|| format!("invalid minutes in timestamp '{trimmed}'"))?;
+            let seconds_nanos = parse_seconds(seconds)?;
+
+            checked_sum(
+                &[checked_mul(minutes, NANOS_PER_MINUTE, trimmed)?, seconds_nanos],
+                trimmed,
+            )?
+        }
+        [value] => {
+            if let (Some(fps), Ok(frames)) = (fps, value.parse::<u64>()) {
+                if !value.contains('.') {
+                    (frames as f64 / fps * NANOS_PER_SECOND as f64).round() as u64
+                } else {
+                    parse_seconds(value)?
+                }
+            } else {
+                parse_seconds(value)?
+            }
+        }
+        _ => anyhow::bail!("invalid timestamp '{trimmed}'"),
+    };
+
+    Ok(Time::from_nanos(nanos))
+}
+
+/// Parse a `SS` or `SS.fraction` field into whole nanoseconds.
+fn parse_seconds(field: &str) -> anyhow::Result<u64> {
+    let (whole, fraction) = field.split_once('.').unwrap_or((field, ""));
+
+    let whole: u64 = whole
+        .parse()
+        .with_context(|| format!("invalid seconds in timestamp field '{field}'"))?;
+
+    if fraction.len() > 9 {
+        anyhow::bail!("fractional seconds '{fraction}' has more than 9 digits");
+    }
+    let mut padded = fraction.to_string();
+    padded.push_str(&"0".repeat(9 - fraction.len()));
+    let fraction_nanos: u64 = if padded.is_empty() {
+        0
+    } else {
+        padded
+            .parse()
+            .with_context(|| format!("invalid fractional seconds in '{field}'"))?
+    };
+
+    checked_sum(&[checked_mul(whole, NANOS_PER_SECOND, field)?, fraction_nanos], field)
+}
+
+/// `value * nanos_per_unit`, as a parse error instead of a panic on overflow.
+fn checked_mul(value: u64, nanos_per_unit: u64, context: &str) -> anyhow::Result<u64> {
+    value
+        .checked_mul(nanos_per_unit)
+        .with_context(|| format!("timestamp '{context}' is out of range"))
+}
+
+/// Sum of `values`, as a parse error instead of a panic on overflow.
+fn checked_sum(values: &[u64], context: &str) -> anyhow::Result<u64> {
+    values
+        .iter()
+        .try_fold(0u64, |acc, &value| acc.checked_add(value))
+        .with_context(|| format!("timestamp '{context}' is out of range"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_full_hms_with_fraction() {
+        let time = parse_time("01:02:03.5").unwrap();
+        assert_eq!(
+            time.as_nanos(),
+            1 * NANOS_PER_HOUR + 2 * NANOS_PER_MINUTE + 3 * NANOS_PER_SECOND + 500_000_000
+        );
+    }
+
+    #[test]
+    fn parses_minutes_and_seconds() {
+        let time = parse_time("01:30").unwrap();
+        assert_eq!(time.as_nanos(), 90 * NANOS_PER_SECOND);
+    }
+
+    #[test]
+    fn parses_bare_seconds() {
+        let time = parse_time("93.5").unwrap();
+        assert_eq!(time.as_nanos(), 93 * NANOS_PER_SECOND + 500_000_000);
+    }
+
+    #[test]
+    fn pads_short_fractions_to_nanoseconds() {
+        let time = parse_time("1.5").unwrap();
+        assert_eq!(time.as_nanos(), NANOS_PER_SECOND + 500_000_000);
+    }
+
+    #[test]
+    fn rejects_fractions_longer_than_nine_digits() {
+        assert!(parse_time("1.1234567890").is_err());
+    }
+
+    #[test]
+    fn bare_integer_is_seconds_without_fps() {
+        let time = parse_time_with_fps("48", None).unwrap();
+        assert_eq!(time.as_nanos(), 48 * NANOS_PER_SECOND);
+    }
+
+    #[test]
+    fn bare_integer_is_a_frame_count_with_fps() {
+        let time = parse_time_with_fps("48", Some(24.0)).unwrap();
+        assert_eq!(time.as_nanos(), 2 * NANOS_PER_SECOND);
+    }
+
+    #[test]
+    fn fractional_value_is_still_seconds_even_with_fps() {
+        let time = parse_time_with_fps("1.5", Some(24.0)).unwrap();
+        assert_eq!(time.as_nanos(), NANOS_PER_SECOND + 500_000_000);
+    }
+
+    #[test]
+    fn rejects_empty_input() {
+        assert!(parse_time("").is_err());
+    }
+
+    #[test]
+    fn rejects_garbage_input() {
+        assert!(parse_time("not-a-timestamp").is_err());
+    }
+
+    #[test]
+    fn displays_canonical_form() {
+        let time = parse_time("01:02:03.5").unwrap();
+        assert_eq!(time.to_string(), "01:02:03.500000000");
+    }
+
+    #[test]
+    fn huge_hour_field_errors_instead_of_panicking() {
+        assert!(parse_time("600000000:00:00").is_err());
+    }
+
+    #[test]
+    fn huge_bare_seconds_errors_instead_of_panicking() {
+        assert!(parse_time(&u64::MAX.to_string()).is_err());
+    }
+}